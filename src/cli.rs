@@ -1,8 +1,10 @@
+use crate::config;
+use crate::screenshot::CaptureMode;
+use crate::target::Target;
 use clap::{Parser, Subcommand};
 use global_hotkey::hotkey::{Code, Modifiers};
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use std::fs;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -23,6 +25,30 @@ pub enum Commands {
         #[arg(short, long)]
         background: bool,
     },
+    /// Take a screenshot and send it to Claude Code
+    Capture {
+        /// What to capture
+        #[arg(short, long, value_enum, default_value_t = CaptureMode::Region)]
+        mode: CaptureMode,
+        /// Session to send to: pid:<n>, tty:<path>, cwd:<dir> or name:<label>;
+        /// "all" or a comma-separated list sends to several
+        #[arg(short, long)]
+        target: Option<Target>,
+    },
+    /// Send an existing image to Claude Code
+    Send {
+        /// Path of the image to send
+        path: PathBuf,
+        /// Session to send to: pid:<n>, tty:<path>, cwd:<dir> or name:<label>;
+        /// "all" or a comma-separated list sends to several
+        #[arg(short, long)]
+        target: Option<Target>,
+    },
+    /// Pin the Claude Code session captures are sent to by default
+    Target {
+        #[command(subcommand)]
+        action: TargetAction,
+    },
     /// Stop the background daemon
     Stop,
     /// Check daemon status
@@ -55,11 +81,40 @@ pub enum Commands {
         #[arg(short, long)]
         show: bool,
     },
+    /// List terminal backends and configure the order they are tried in
+    Backends {
+        /// Set the order backends are tried in (e.g., "nvim,iterm2,terminal")
+        #[arg(short, long)]
+        order: Option<String>,
+        /// Enable a previously disabled backend
+        #[arg(short, long)]
+        enable: Option<String>,
+        /// Disable a backend so it is never used
+        #[arg(short, long)]
+        disable: Option<String>,
+    },
+    /// List detected Claude Code sessions and how paparazzi would reach them
+    Sessions {
+        /// Print as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
     /// Display version information
     Version,
 }
 
+#[derive(Subcommand)]
+pub enum TargetAction {
+    /// Pin a session: pid:<n>, tty:<path>, cwd:<dir> or name:<label>
+    Set { target: Target },
+    /// Unpin the session
+    Clear,
+    /// Show the pinned session
+    Show,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HotkeyConfig {
     pub modifiers: String,
     pub key: String,
@@ -94,6 +149,19 @@ impl HotkeyConfig {
         })
     }
 
+    /// Parses "<modifiers>+<key>", e.g. "ctrl+shift+s"
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let (modifiers_str, key_str) = spec
+            .rsplit_once('+')
+            .ok_or_else(|| format!("Invalid hotkey: {}", spec))?;
+        Self::from_strings(modifiers_str, key_str)
+    }
+
+    /// The hotkey as "<modifiers>+<key>", the form `from_spec` accepts
+    pub fn spec(&self) -> String {
+        format!("{}+{}", self.modifiers, self.key)
+    }
+
     pub fn parse(&mut self) -> Result<(), String> {
         self.modifiers_parsed = Some(parse_modifiers(&self.modifiers)?);
         self.key_parsed = Some(parse_key(&self.key)?);
@@ -249,33 +317,16 @@ fn format_key(code: &Code) -> String {
     .to_string()
 }
 
-fn get_config_path() -> PathBuf {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("paparazzi");
-
-    fs::create_dir_all(&config_dir).ok();
-    config_dir.join("config.json")
-}
-
-pub fn save_hotkey_config(config: &HotkeyConfig) -> Result<(), String> {
-    let config_path = get_config_path();
-    let json = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-    fs::write(&config_path, json).map_err(|e| format!("Failed to write config file: {}", e))?;
-
-    Ok(())
+pub fn save_hotkey_config(hotkey: &HotkeyConfig) -> Result<(), String> {
+    let mut config = config::load_config();
+    config.hotkey = hotkey.clone();
+    config::save_config(&config)
 }
 
 pub fn load_hotkey_config() -> HotkeyConfig {
-    let config_path = get_config_path();
+    let mut config = config::load_config().hotkey;
 
-    if config_path.exists()
-        && let Ok(contents) = fs::read_to_string(&config_path)
-        && let Ok(mut config) = serde_json::from_str::<HotkeyConfig>(&contents)
-        && config.parse().is_ok()
-    {
+    if config.parse().is_ok() {
         return config;
     }
 
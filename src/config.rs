@@ -0,0 +1,65 @@
+use crate::cli::HotkeyConfig;
+use crate::logger;
+use crate::routing::RouteRule;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Hotkey fields stay at the top level so config files written by older
+    // versions keep loading
+    #[serde(flatten)]
+    pub hotkey: HotkeyConfig,
+    pub backends: BackendsConfig,
+    /// Rules picking the session a capture goes to, first match wins
+    pub routes: Vec<RouteRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendsConfig {
+    /// Backends to try first, in this order. Unlisted backends follow in their default order.
+    pub order: Vec<String>,
+    /// Backends that are never used
+    pub disabled: Vec<String>,
+}
+
+pub fn get_config_path() -> PathBuf {
+    let config_dir = dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("paparazzi");
+
+    fs::create_dir_all(&config_dir).ok();
+    config_dir.join("config.json")
+}
+
+pub fn load_config() -> Config {
+    let config_path = get_config_path();
+
+    if config_path.exists()
+        && let Ok(contents) = fs::read_to_string(&config_path)
+    {
+        match serde_json::from_str::<Config>(&contents) {
+            Ok(config) => return config,
+            Err(e) => logger::warning(&format!(
+                "Ignoring invalid config file {}: {}",
+                config_path.display(),
+                e
+            )),
+        }
+    }
+
+    Config::default()
+}
+
+pub fn save_config(config: &Config) -> Result<(), String> {
+    let config_path = get_config_path();
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    fs::write(&config_path, json).map_err(|e| format!("Failed to write config file: {}", e))?;
+
+    Ok(())
+}
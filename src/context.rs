@@ -0,0 +1,117 @@
+use crate::screenshot::CaptureMode;
+
+/// What the user was looking at and how they captured it.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureContext {
+    pub app: Option<String>,
+    pub window_title: Option<String>,
+    pub mode: CaptureMode,
+    /// Hotkey that triggered the capture, as "<modifiers>+<key>"
+    pub hotkey: Option<String>,
+}
+
+impl CaptureContext {
+    /// Records the focused window. Call before capturing, while the user's
+    /// window is still in front.
+    pub fn collect(mode: CaptureMode, hotkey: Option<String>) -> Self {
+        let (app, window_title) = focused_window();
+
+        CaptureContext {
+            app,
+            window_title,
+            mode,
+            hotkey,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+const FOCUSED_WINDOW_SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    set windowTitle to ""
+    try
+        set windowTitle to name of front window of frontApp
+    end try
+end tell
+return appName & tab & windowTitle"#;
+
+// Application name and window title of the focused window
+#[cfg(target_os = "macos")]
+fn focused_window() -> (Option<String>, Option<String>) {
+    use std::process::Command;
+
+    let Ok(output) = Command::new("osascript")
+        .arg("-e")
+        .arg(FOCUSED_WINDOW_SCRIPT)
+        .output()
+    else {
+        return (None, None);
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (app, title) = stdout.trim_end().split_once('\t').unwrap_or((&stdout, ""));
+
+    (non_empty(app), non_empty(title))
+}
+
+// Application name (WM_CLASS) and window title of the focused X window
+#[cfg(target_os = "linux")]
+fn focused_window() -> (Option<String>, Option<String>) {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    let focused = || -> anyhow::Result<(Option<String>, Option<String>)> {
+        let (conn, screen) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen].root;
+        let atom = |name: &str| -> anyhow::Result<u32> {
+            Ok(conn.intern_atom(false, name.as_bytes())?.reply()?.atom)
+        };
+
+        let active = conn
+            .get_property(
+                false,
+                root,
+                atom("_NET_ACTIVE_WINDOW")?,
+                AtomEnum::WINDOW,
+                0,
+                1,
+            )?
+            .reply()?
+            .value32()
+            .and_then(|mut v| v.next())
+            .filter(|window| *window != 0)
+            .ok_or_else(|| anyhow::anyhow!("No active window"))?;
+
+        // WM_CLASS is "instance\0class\0"; the class is the app's proper name
+        let class = conn
+            .get_property(false, active, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)?
+            .reply()?
+            .value;
+        let app = class
+            .split(|b| *b == 0)
+            .rfind(|part| !part.is_empty())
+            .map(|part| String::from_utf8_lossy(part).to_string());
+
+        let utf8_string = atom("UTF8_STRING")?;
+        let mut title = conn
+            .get_property(false, active, atom("_NET_WM_NAME")?, utf8_string, 0, 1024)?
+            .reply()?
+            .value;
+        if title.is_empty() {
+            title = conn
+                .get_property(false, active, AtomEnum::WM_NAME, AtomEnum::STRING, 0, 1024)?
+                .reply()?
+                .value;
+        }
+
+        Ok((app, non_empty(&String::from_utf8_lossy(&title))))
+    };
+
+    focused().unwrap_or((None, None))
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
@@ -1,20 +1,31 @@
 use anyhow::Result;
 use clap::Parser;
-use cli::{Cli, Commands, HotkeyConfig};
+use cli::{Cli, Commands, HotkeyConfig, TargetAction};
+use context::CaptureContext;
 use daemon::show_logs;
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, hotkey::HotKey};
+use screenshot::CaptureMode;
+use serde::Serialize;
+use std::path::Path;
+use target::Target;
 use winit::application::ApplicationHandler;
 use winit::event_loop::{ControlFlow, EventLoop};
 
 mod cli;
+mod config;
 mod constants;
+mod context;
 mod daemon;
 mod logger;
+mod process;
+mod routing;
 mod screenshot;
+mod target;
 mod terminal;
 
 struct App {
     receiver: crossbeam_channel::Receiver<GlobalHotKeyEvent>,
+    hotkey: String,
 }
 
 impl ApplicationHandler for App {
@@ -39,7 +50,9 @@ impl ApplicationHandler for App {
             && event.state == global_hotkey::HotKeyState::Pressed
         {
             logger::info("Hotkey pressed! Taking screenshot...");
-            if let Err(err) = handle_screenshot() {
+            if let Err(err) =
+                handle_screenshot(None, CaptureMode::Region, Some(self.hotkey.clone()))
+            {
                 eprintln!("Error: {}", err);
             }
         }
@@ -61,6 +74,18 @@ fn main() -> Result<()> {
                 run_service_internal()?;
             }
         }
+        Some(Commands::Capture { mode, target }) => {
+            handle_screenshot(target.as_ref(), mode, None)?;
+        }
+        Some(Commands::Send { path, target }) => {
+            if !path.exists() {
+                anyhow::bail!("No such file: {}", path.display());
+            }
+            send_screenshot(&path.canonicalize()?, target.as_ref(), None)?;
+        }
+        Some(Commands::Target { action }) => {
+            handle_target_command(action)?;
+        }
         Some(Commands::Stop) => {
             daemon.stop()?;
         }
@@ -81,6 +106,16 @@ fn main() -> Result<()> {
         Some(Commands::Logging { level, show }) => {
             handle_logging_command(level, show)?;
         }
+        Some(Commands::Backends {
+            order,
+            enable,
+            disable,
+        }) => {
+            handle_backends_command(order, enable, disable)?;
+        }
+        Some(Commands::Sessions { json }) => {
+            handle_sessions_command(json)?;
+        }
         Some(Commands::Version) => {
             print_version();
         }
@@ -113,7 +148,10 @@ fn run_service_internal() -> Result<()> {
     logger::success("Hotkey registered successfully");
 
     let receiver = GlobalHotKeyEvent::receiver().to_owned();
-    let mut app = App { receiver };
+    let mut app = App {
+        receiver,
+        hotkey: config.spec(),
+    };
 
     logger::info("Service is running...");
     event_loop.run_app(&mut app)?;
@@ -257,6 +295,219 @@ fn handle_logging_command(level: Option<String>, show: bool) -> Result<()> {
     Ok(())
 }
 
+fn handle_target_command(action: TargetAction) -> Result<()> {
+    match action {
+        TargetAction::Set { target } => {
+            if target.is_broadcast() {
+                anyhow::bail!(
+                    "Only a single session can be pinned, use --target {} per capture instead",
+                    target
+                );
+            }
+            let sessions = process::find_claude_code_processes()?;
+            let session = terminal::select_sessions(sessions, &target)?.remove(0);
+
+            target::save_pinned_target(&target::PinnedTarget {
+                target: target.to_string(),
+                pid: session.pid,
+                start_time: session.start_time,
+            })?;
+            logger::success("Target pinned!");
+            println!(
+                "   Captures will be sent to {} (PID {})",
+                target, session.pid
+            );
+            println!("   until that session exits or you run 'paparazzi target clear'");
+        }
+        TargetAction::Clear => {
+            target::clear_pinned_target()?;
+            logger::success("Target cleared");
+            println!("   Captures will be sent to the first Claude Code session found");
+        }
+        TargetAction::Show => match target::load_pinned_target() {
+            Some(pin) => {
+                println!("Pinned target: {}", pin.target);
+                println!("   PID: {}", pin.pid);
+            }
+            None => {
+                println!("No target pinned");
+                println!(
+                    "\nPin one with: paparazzi target set pid:<n>|tty:<path>|cwd:<dir>|name:<label>"
+                );
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn handle_backends_command(
+    order: Option<String>,
+    enable: Option<String>,
+    disable: Option<String>,
+) -> Result<()> {
+    let mut config = config::load_config();
+    let known: Vec<&str> = terminal::all_backends().iter().map(|b| b.name()).collect();
+
+    let order: Option<Vec<String>> = order.map(|order_str| {
+        order_str
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+    let enable = enable.map(|name| name.trim().to_lowercase());
+    let disable = disable.map(|name| name.trim().to_lowercase());
+
+    let requested = order
+        .iter()
+        .flatten()
+        .chain(enable.iter())
+        .chain(disable.iter());
+    for name in requested {
+        if !known.contains(&name.as_str()) {
+            eprintln!("Unknown backend: {}", name);
+            eprintln!("\nAvailable backends: {}", known.join(", "));
+            return Ok(());
+        }
+    }
+
+    if order.is_some() || enable.is_some() || disable.is_some() {
+        if let Some(order) = order {
+            config.backends.order = order;
+        }
+        if let Some(name) = enable {
+            config.backends.disabled.retain(|n| *n != name);
+        }
+        if let Some(name) = disable
+            && !config.backends.disabled.contains(&name)
+        {
+            config.backends.disabled.push(name);
+        }
+
+        config::save_config(&config).map_err(|e| anyhow::anyhow!(e))?;
+        logger::success("Backend configuration updated!");
+        println!();
+    }
+
+    println!("Terminal backends (in delivery order):");
+    for (index, backend) in terminal::configured_backends(&config.backends)
+        .iter()
+        .enumerate()
+    {
+        let status = if backend.detect() {
+            "available"
+        } else {
+            "not available"
+        };
+        println!(
+            "   {}. {:<10} {:<15} {}",
+            index + 1,
+            backend.name(),
+            status,
+            backend.description()
+        );
+    }
+    for name in &config.backends.disabled {
+        println!("   -  {:<10} disabled", name);
+    }
+
+    println!();
+    println!("To change the order or disable a backend:");
+    println!("  paparazzi backends --order nvim,iterm2,terminal");
+    println!("  paparazzi backends --disable ghostty");
+    println!("  paparazzi backends --enable ghostty");
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SessionReport {
+    pid: i32,
+    tty: Option<String>,
+    cwd: Option<String>,
+    uptime_secs: u64,
+    terminal: Option<String>,
+    /// Multiplexers and remote logins, nearest to Claude first
+    multiplexers: Vec<String>,
+    remote: bool,
+    backend: Option<String>,
+    backend_session: Option<String>,
+}
+
+fn handle_sessions_command(json: bool) -> Result<()> {
+    let sessions = process::find_claude_code_processes().unwrap_or_default();
+    let routes = terminal::route_sessions(&sessions);
+
+    let reports: Vec<SessionReport> = sessions
+        .iter()
+        .zip(routes)
+        .map(|(session, route)| SessionReport {
+            pid: session.pid,
+            tty: session.tty.clone(),
+            cwd: session.cwd.as_ref().map(|cwd| cwd.display().to_string()),
+            uptime_secs: session.uptime(),
+            terminal: session.chain.emulator().map(|layer| layer.name.to_string()),
+            multiplexers: session
+                .chain
+                .multiplexers()
+                .map(|layer| layer.name.to_string())
+                .collect(),
+            remote: session.chain.is_remote(),
+            backend: route.as_ref().map(|(backend, _)| backend.to_string()),
+            backend_session: route.map(|(_, s)| s.title.unwrap_or(s.id)),
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+
+    if reports.is_empty() {
+        println!("No Claude Code sessions found");
+        println!("\nStart Claude Code in a terminal and try again");
+        return Ok(());
+    }
+
+    println!("Claude Code sessions:");
+    for report in &reports {
+        println!();
+        println!("   PID: {}", report.pid);
+        println!("   TTY: {}", report.tty.as_deref().unwrap_or("-"));
+        println!("   CWD: {}", report.cwd.as_deref().unwrap_or("-"));
+        println!("   Uptime: {}", format_duration(report.uptime_secs));
+        println!(
+            "   Terminal: {}{}",
+            report
+                .terminal
+                .as_deref()
+                .unwrap_or(if report.remote { "remote" } else { "unknown" }),
+            if report.multiplexers.is_empty() {
+                String::new()
+            } else {
+                format!(" (via {})", report.multiplexers.join(", "))
+            }
+        );
+        match (&report.backend, &report.backend_session) {
+            (Some(backend), Some(session)) => println!("   Backend: {} ({})", backend, session),
+            _ => println!("   Backend: none available"),
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
+
 fn print_version() {
     println!("paparazzi {}", env!("CARGO_PKG_VERSION"));
     println!("A CLI tool for instant screenshots to Claude Code");
@@ -273,6 +524,12 @@ fn print_intro() {
     println!("COMMANDS:");
     println!("  run       Start the screenshot service");
     println!("    --background, -b    Run as background daemon");
+    println!("  capture   Take a screenshot and send it to Claude Code");
+    println!("    --mode, -m          region (default), window or fullscreen");
+    println!("    --target, -t        Session(s) to send to (pid:, tty:, cwd:, name:, all)");
+    println!("  send      Send an existing image to Claude Code");
+    println!("    --target, -t        Session(s) to send to (pid:, tty:, cwd:, name:, all)");
+    println!("  target    Pin the session captures are sent to (set, clear, show)");
     println!("  stop      Stop the background daemon");
     println!("  status    Check daemon status");
     println!("  logs      View daemon logs");
@@ -282,6 +539,12 @@ fn print_intro() {
     println!("  logging   Configure logging settings");
     println!("    --level, -l         Set log level (info, success, error, warning, all, off)");
     println!("    --show, -s          Show current logging configuration");
+    println!("  sessions  List detected Claude Code sessions");
+    println!("    --json, -j          Print as JSON");
+    println!("  backends  List terminal backends and configure delivery order");
+    println!("    --order, -o         Set the order backends are tried in");
+    println!("    --enable, -e        Enable a backend");
+    println!("    --disable, -d       Disable a backend");
     println!("  version   Display version information");
     println!("  help      Display this help message");
     println!();
@@ -293,6 +556,10 @@ fn print_intro() {
     println!("EXAMPLES:");
     println!("  paparazzi run                                    # Run in foreground");
     println!("  paparazzi run --background                       # Run as daemon");
+    println!("  paparazzi capture --target cwd:~/code/app        # Capture to a session");
+    println!("  paparazzi send shot.png --target pid:4242        # Send an existing image");
+    println!("  paparazzi send shot.png --target name:web,name:api  # Send to two sessions");
+    println!("  paparazzi target set name:backend                # Pin a session");
     println!("  paparazzi stop                                   # Stop daemon");
     println!("  paparazzi status                                 # Check daemon status");
     println!("  paparazzi logs                                   # View daemon logs");
@@ -300,19 +567,74 @@ fn print_intro() {
     println!("  paparazzi hotkeys --modifiers \"ctrl+shift\" --key s  # Set new hotkey");
     println!("  paparazzi logging --show                         # Show log level");
     println!("  paparazzi logging --level off                    # Disable logging");
+    println!("  paparazzi sessions                               # Show Claude Code sessions");
+    println!("  paparazzi backends                               # Show terminal backends");
     println!();
     println!("For more information, visit: https://github.com/benodiwal/paparazzi");
     println!("\n Bye\n");
 }
 
-fn handle_screenshot() -> Result<()> {
-    let screenshot_path = screenshot::capture()?;
+fn handle_screenshot(
+    target: Option<&Target>,
+    mode: CaptureMode,
+    hotkey: Option<String>,
+) -> Result<()> {
+    let context = CaptureContext::collect(mode, hotkey);
+    let screenshot_path = screenshot::capture(mode)?;
     logger::info(&format!("Screenshot saved to: {}", screenshot_path));
 
-    let message = format!("{} Analyze this image", screenshot_path);
-    terminal::send_to_claude_code_terminal(&message)?;
+    send_screenshot(Path::new(&screenshot_path), target, Some(&context))
+}
+
+fn send_screenshot(
+    path: &Path,
+    target: Option<&Target>,
+    context: Option<&CaptureContext>,
+) -> Result<()> {
+    let config = config::load_config();
+    let target = routing::resolve_target(target, context, &config.routes);
+
+    let message = format!("{} Analyze this image", path.display());
+
+    if let Some(target) = target.as_ref().filter(|target| target.is_broadcast()) {
+        return broadcast_screenshot(&message, target);
+    }
+
+    terminal::send_to_claude_code_terminal(&message, target.as_ref())?;
 
     println!("Sent to Claude Code!");
 
     Ok(())
 }
+
+fn broadcast_screenshot(message: &str, target: &Target) -> Result<()> {
+    let deliveries = terminal::broadcast_to_claude_code_sessions(message, target)?;
+    let sent = deliveries.iter().filter(|d| d.result.is_ok()).count();
+
+    println!(
+        "Sent to {} of {} Claude Code sessions:",
+        sent,
+        deliveries.len()
+    );
+    for delivery in &deliveries {
+        let cwd = delivery
+            .session
+            .cwd
+            .as_ref()
+            .map(|cwd| cwd.display().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        match &delivery.result {
+            Ok(via) => println!(
+                "   PID {} ({}): sent via {}",
+                delivery.session.pid, cwd, via
+            ),
+            Err(e) => println!("   PID {} ({}): failed: {}", delivery.session.pid, cwd, e),
+        }
+    }
+
+    if sent == 0 {
+        anyhow::bail!("Could not send to any Claude Code session");
+    }
+
+    Ok(())
+}
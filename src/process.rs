@@ -0,0 +1,389 @@
+use anyhow::Result;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+mod multiplexer;
+
+// Executable names Claude Code runs under: the native installer's binary, the
+// npm shim, and the process title the CLI sets for itself
+const CLAUDE_NAMES: &[&str] = &["claude", "claude-code"];
+
+// Runtimes the npm package can be launched through (`node .../cli.js`)
+const SCRIPT_RUNTIMES: &[&str] = &["node", "bun", "deno"];
+
+const TERMINAL_EMULATORS: &[&str] = &[
+    "iTerm2",
+    "Terminal",
+    "ghostty",
+    "kitty",
+    "alacritty",
+    "wezterm-gui",
+    "konsole",
+    "yakuake",
+    "gnome-terminal-server",
+    "xfce4-terminal",
+    "terminator",
+    "tilix",
+    "xterm",
+    "urxvt",
+    "foot",
+    "nvim",
+];
+
+const MULTIPLEXERS: &[&str] = &["tmux", "screen", "zellij"];
+
+// Daemons of remote logins. Above these is the local system, not a terminal.
+const REMOTE_SHELLS: &[&str] = &["sshd", "sshd-session", "mosh-server"];
+
+// Multiplexer clients to follow out of their server, at most
+const MAX_MULTIPLEXER_HOPS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: i32,
+    pub name: String,
+}
+
+/// A running Claude Code process and where it lives.
+#[derive(Debug, Clone)]
+pub struct ClaudeSession {
+    pub pid: i32,
+    pub tty: Option<String>,
+    pub cwd: Option<PathBuf>,
+    /// Seconds since the Unix epoch
+    pub start_time: u64,
+    /// Parent, grandparent, ... up to init, nearest first
+    pub parents: Vec<ProcessInfo>,
+    pub chain: TerminalChain,
+}
+
+impl ClaudeSession {
+    pub fn has_ancestor(&self, pid: i32) -> bool {
+        self.parents.iter().any(|parent| parent.pid == pid)
+    }
+
+    pub fn uptime(&self) -> u64 {
+        (chrono::Utc::now().timestamp() as u64).saturating_sub(self.start_time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    Emulator,
+    Multiplexer,
+    /// A remote login; the terminal emulator runs on another machine
+    Remote,
+}
+
+#[derive(Debug, Clone)]
+pub struct TerminalLayer {
+    pub kind: LayerKind,
+    pub name: &'static str,
+    pub pid: i32,
+}
+
+/// The terminal emulator, multiplexers and remote logins between the screen
+/// and a Claude process.
+#[derive(Debug, Clone, Default)]
+pub struct TerminalChain {
+    /// Nearest to Claude first; the emulator, when found, is last
+    pub layers: Vec<TerminalLayer>,
+    /// Processes outside Claude's own ancestry whose terminal shows Claude
+    /// and passes keystrokes to it, i.e. the multiplexer clients displaying
+    /// its pane and their ancestors
+    pub outer_pids: Vec<i32>,
+    /// Terminals of those multiplexer clients
+    pub outer_ttys: Vec<String>,
+}
+
+impl TerminalChain {
+    pub fn emulator(&self) -> Option<&TerminalLayer> {
+        self.layers
+            .iter()
+            .find(|layer| layer.kind == LayerKind::Emulator)
+    }
+
+    /// Multiplexers and remote logins, nearest to Claude first
+    pub fn multiplexers(&self) -> impl Iterator<Item = &TerminalLayer> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.kind != LayerKind::Emulator)
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.kind == LayerKind::Remote)
+    }
+}
+
+impl std::fmt::Display for TerminalChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.layers.iter().rev().map(|layer| layer.name).collect();
+        write!(f, "{}", names.join(" > "))
+    }
+}
+
+pub fn find_claude_code_processes() -> Result<Vec<ClaudeSession>> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cmd(UpdateKind::Always)
+            .with_exe(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always)
+            .with_environ(UpdateKind::Always),
+    );
+
+    let own_pid = sysinfo::get_current_pid().ok();
+    let matches: Vec<Pid> = sys
+        .processes()
+        .iter()
+        .filter(|(pid, process)| {
+            // Linux lists threads as processes too
+            process.thread_kind().is_none() && Some(**pid) != own_pid && is_claude_code(process)
+        })
+        .map(|(pid, _)| *pid)
+        .collect();
+
+    let mut sessions = Vec::new();
+    for pid in &matches {
+        let parents = parent_chain(&sys, *pid);
+
+        // Claude running `claude -p` through its own shell tool is not a
+        // separate session
+        if parents
+            .iter()
+            .any(|parent| matches.contains(&Pid::from(parent.pid as usize)))
+        {
+            continue;
+        }
+
+        let process = &sys.processes()[pid];
+        let chain = terminal_chain(&sys, *pid, &parents);
+        let pid = pid.as_u32() as i32;
+        sessions.push(ClaudeSession {
+            pid,
+            tty: find_terminal_for_process(pid).ok(),
+            cwd: process.cwd().map(Path::to_path_buf),
+            start_time: process.start_time(),
+            parents,
+            chain,
+        });
+    }
+
+    if sessions.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No Claude Code processes found. Please make sure Claude Code is running."
+        ));
+    }
+
+    sessions.sort_by_key(|session| (session.start_time, session.pid));
+    Ok(sessions)
+}
+
+fn is_claude_code(process: &Process) -> bool {
+    let cmd = process.cmd();
+    let is_claude_name = |name: Option<&OsStr>| {
+        name.and_then(OsStr::to_str)
+            .is_some_and(|name| CLAUDE_NAMES.contains(&name))
+    };
+
+    // Native binary or shim: matched by process name, executable or argv[0].
+    // The native binary's file is named after its version, so the executable
+    // alone isn't enough.
+    if is_claude_name(Some(process.name()))
+        || is_claude_name(process.exe().and_then(Path::file_name))
+        || is_claude_name(cmd.first().and_then(|arg0| Path::new(arg0).file_name()))
+    {
+        return true;
+    }
+
+    // `node [flags] /path/to/claude-code/cli.js ...`: the script is the first
+    // non-flag argument
+    let runtime = process.name().to_str().unwrap_or_default();
+    if !SCRIPT_RUNTIMES.contains(&runtime) {
+        return false;
+    }
+
+    cmd.iter()
+        .skip(1)
+        .filter_map(|arg| arg.to_str())
+        .find(|arg| !arg.starts_with('-'))
+        .is_some_and(|script| {
+            script.contains("@anthropic-ai/claude-code/")
+                || is_claude_name(Path::new(script).file_name())
+        })
+}
+
+fn parent_chain(sys: &System, pid: Pid) -> Vec<ProcessInfo> {
+    let mut parents: Vec<ProcessInfo> = Vec::new();
+    let mut current = pid;
+
+    while let Some(parent) = sys.process(current).and_then(Process::parent) {
+        let parent_pid = parent.as_u32() as i32;
+        if parent_pid == 0 || parents.iter().any(|p| p.pid == parent_pid) {
+            break;
+        }
+
+        let name = sys
+            .process(parent)
+            .map(|p| p.name().to_string_lossy().to_string())
+            .unwrap_or_default();
+        parents.push(ProcessInfo {
+            pid: parent_pid,
+            name,
+        });
+        current = parent;
+    }
+
+    parents
+}
+
+// Walks out from Claude to the terminal emulator, skipping the shells in
+// between. Multiplexer servers run detached from any terminal, so the walk
+// continues from the client attached to Claude's pane instead.
+fn terminal_chain(sys: &System, pid: Pid, parents: &[ProcessInfo]) -> TerminalChain {
+    let mut chain = TerminalChain::default();
+    let mut ancestors = parents.to_vec();
+    let mut child = pid.as_u32() as i32;
+    // Environment of a process in the pane being walked out of, which names
+    // the multiplexer session it belongs to
+    let mut env = process_env(sys, pid);
+    // Keys typed into a client only reach the pane it shows
+    let mut reachable = true;
+    let mut hops = 0;
+    let mut index = 0;
+
+    while let Some(parent) = ancestors.get(index) {
+        let layer = |kind, name| TerminalLayer {
+            kind,
+            name,
+            pid: parent.pid,
+        };
+        if hops > 0 && reachable {
+            chain.outer_pids.push(parent.pid);
+        }
+
+        if let Some(name) = known_name(&parent.name, TERMINAL_EMULATORS) {
+            chain.layers.push(layer(LayerKind::Emulator, name));
+            break;
+        }
+        if let Some(name) = known_name(&parent.name, REMOTE_SHELLS) {
+            chain.layers.push(layer(LayerKind::Remote, name));
+            break;
+        }
+        if let Some(name) = known_name(&parent.name, MULTIPLEXERS) {
+            chain.layers.push(layer(LayerKind::Multiplexer, name));
+
+            if hops == MAX_MULTIPLEXER_HOPS {
+                break;
+            }
+            let Some(client) = multiplexer::client(sys, name, parent.pid, child, &env) else {
+                break;
+            };
+            hops += 1;
+
+            let client_pid = Pid::from(client.pid as usize);
+            reachable &= client.shows_pane;
+            if reachable {
+                chain.outer_pids.push(client.pid);
+                chain.outer_ttys.extend(client.tty);
+            }
+            // The client runs in the next terminal out, possibly another
+            // multiplexer's pane
+            env = process_env(sys, client_pid);
+            ancestors = parent_chain(sys, client_pid);
+            child = client.pid;
+            index = 0;
+            continue;
+        }
+
+        child = parent.pid;
+        index += 1;
+    }
+
+    chain
+}
+
+fn process_env(sys: &System, pid: Pid) -> Vec<(String, String)> {
+    sys.process(pid)
+        .map(|process| {
+            process
+                .environ()
+                .iter()
+                .filter_map(|var| var.to_str()?.split_once('='))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn known_name(name: &str, names: &[&'static str]) -> Option<&'static str> {
+    names.iter().copied().find(|known| {
+        // tmux renames its server process to "tmux: server", sshd its
+        // per-login processes to "sshd: user@pts/0"
+        name.eq_ignore_ascii_case(known) || name.starts_with(&format!("{}:", known))
+    })
+}
+
+/// Controlling terminal of a process, e.g. `/dev/pts/3` or `/dev/ttys004`.
+#[cfg(target_os = "linux")]
+pub fn find_terminal_for_process(pid: i32) -> Result<String> {
+    // /proc/<pid>/stat is "pid (comm) state ppid pgrp session tty_nr ...", and
+    // comm may itself contain spaces and parentheses
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let tty_nr: u32 = stat
+        .rsplit_once(')')
+        .and_then(|(_, rest)| rest.split_whitespace().nth(4))
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Could not parse /proc/{}/stat", pid))?;
+
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+
+    match major {
+        0 => Err(anyhow::anyhow!("No TTY found for process")),
+        // Unix98 pseudo-terminals
+        136..=143 => Ok(format!("/dev/pts/{}", (major - 136) * 256 + minor)),
+        // Virtual consoles
+        4 if minor < 64 => Ok(format!("/dev/tty{}", minor)),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported TTY device {}:{}",
+            major,
+            minor
+        )),
+    }
+}
+
+/// Controlling terminal of a process, e.g. `/dev/pts/3` or `/dev/ttys004`.
+#[cfg(not(target_os = "linux"))]
+pub fn find_terminal_for_process(pid: i32) -> Result<String> {
+    use std::process::Command;
+
+    let output = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "tty="])
+        .output()?;
+
+    let tty = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if tty.is_empty() || tty == "??" {
+        return Err(anyhow::anyhow!("No TTY found for process"));
+    }
+
+    let full_tty = if tty.starts_with("/dev/") {
+        tty
+    } else {
+        format!("/dev/{}", tty)
+    };
+
+    Ok(full_tty)
+}
+
+/// Whether an executable is on `$PATH`
+pub fn command_exists(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+}
@@ -0,0 +1,362 @@
+//! Finding the client a multiplexer server shows a pane through. The servers
+//! of tmux, zellij and (once detached) screen run apart from any terminal, so
+//! the terminal emulator is only reachable through an attached client.
+
+use super::find_terminal_for_process;
+use std::process::Command;
+use sysinfo::{Pid, Process, System};
+
+/// A multiplexer client attached to the session a pane belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Client {
+    pub pid: i32,
+    pub tty: Option<String>,
+    /// Whether the client is showing the pane right now, so keys typed into
+    /// its terminal reach the pane's process
+    pub shows_pane: bool,
+}
+
+/// The client of the `name` multiplexer server `server_pid` that shows the
+/// pane `pane_pid` runs in, or failing that any client of the pane's session.
+/// `env` is the environment of a process in the pane.
+pub fn client(
+    sys: &System,
+    name: &str,
+    server_pid: i32,
+    pane_pid: i32,
+    env: &[(String, String)],
+) -> Option<Client> {
+    let var = |key: &str| {
+        env.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    };
+
+    match name {
+        "tmux" => {
+            let server_args: Vec<String> = sys
+                .process(Pid::from(server_pid as usize))
+                .map(|server| {
+                    server
+                        .cmd()
+                        .iter()
+                        .map(|arg| arg.to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            tmux_client(&tmux_socket_args(var("TMUX"), &server_args), pane_pid)
+        }
+        "screen" => screen_client(sys, server_pid, var("STY")?, var("WINDOW")),
+        "zellij" => zellij_client(sys, var("ZELLIJ_SESSION_NAME")?, var("ZELLIJ_PANE_ID")),
+        _ => None,
+    }
+}
+
+// Arguments selecting the server's socket. $TMUX is "<socket path>,<server
+// pid>,<session index>". When the pane's environment isn't readable or has it
+// unset, the server keeps the command line that started it, `-L`/`-S` included.
+fn tmux_socket_args(tmux_env: Option<&str>, server_args: &[String]) -> Vec<String> {
+    if let Some(socket) = tmux_env.and_then(|value| value.split(',').next())
+        && !socket.is_empty()
+    {
+        return vec!["-S".to_string(), socket.to_string()];
+    }
+
+    server_args
+        .windows(2)
+        .find(|pair| pair[0] == "-L" || pair[0] == "-S")
+        .map(|pair| pair.to_vec())
+        .unwrap_or_default()
+}
+
+fn tmux_client(socket_args: &[String], pane_pid: i32) -> Option<Client> {
+    let tmux = |args: &[&str]| -> Option<String> {
+        let output = Command::new("tmux")
+            .args(socket_args)
+            .args(args)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let panes = tmux(&[
+        "list-panes",
+        "-a",
+        "-F",
+        "#{pane_pid}\t#{session_id}\t#{window_id}\t#{pane_active}",
+    ])?;
+    let pane = parse_tmux_pane(&panes, pane_pid)?;
+
+    let clients = tmux(&[
+        "list-clients",
+        "-F",
+        "#{client_pid}\t#{client_tty}\t#{session_id}\t#{window_id}\t#{client_activity}",
+    ])?;
+    pick_tmux_client(&clients, &pane)
+}
+
+#[derive(Debug, PartialEq)]
+struct TmuxPane {
+    session_id: String,
+    window_id: String,
+    active: bool,
+}
+
+fn parse_tmux_pane(output: &str, pane_pid: i32) -> Option<TmuxPane> {
+    output.lines().find_map(
+        |line| match line.split('\t').collect::<Vec<_>>().as_slice() {
+            [pid, session_id, window_id, active] if pid.parse() == Ok(pane_pid) => Some(TmuxPane {
+                session_id: session_id.to_string(),
+                window_id: window_id.to_string(),
+                active: *active == "1",
+            }),
+            _ => None,
+        },
+    )
+}
+
+// Among the clients attached to the pane's session, the most recently active
+// one showing the pane's window, else the most recently active one
+fn pick_tmux_client(output: &str, pane: &TmuxPane) -> Option<Client> {
+    output
+        .lines()
+        .filter_map(
+            |line| match line.split('\t').collect::<Vec<_>>().as_slice() {
+                [pid, tty, session_id, window_id, activity] if *session_id == pane.session_id => {
+                    let shows_pane = pane.active && *window_id == pane.window_id;
+                    let activity: u64 = activity.parse().unwrap_or(0);
+                    let client = Client {
+                        pid: pid.parse().ok()?,
+                        tty: (!tty.is_empty()).then(|| tty.to_string()),
+                        shows_pane,
+                    };
+                    Some(((shows_pane, activity), client))
+                }
+                _ => None,
+            },
+        )
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, client)| client)
+}
+
+// $STY is "<server pid>.<session name>" and $WINDOW the pane's window number.
+// A screen started from a shell keeps its client as the server's parent until
+// the first detach; reattached clients are found by the session in their
+// command line.
+fn screen_client(sys: &System, server_pid: i32, sty: &str, window: Option<&str>) -> Option<Client> {
+    let server = sys.process(Pid::from(server_pid as usize))?;
+
+    let parent = server
+        .parent()
+        .and_then(|parent| sys.process(parent))
+        .filter(|parent| parent.name().eq_ignore_ascii_case("screen"));
+    let client = parent.or_else(|| {
+        single_client(sys, "screen", server_pid, |args| {
+            screen_args_name_session(args, sty)
+        })
+    })?;
+
+    // `-Q number` prints the window the session's display shows, e.g. "2 zsh"
+    let shows_pane = window.is_some_and(|window| {
+        Command::new("screen")
+            .args(["-S", sty, "-Q", "number"])
+            .output()
+            .is_ok_and(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .next()
+                    == Some(window)
+            })
+    });
+
+    Some(to_client(client, shows_pane))
+}
+
+// Whether a screen client's arguments attach it to the session `sty`, as in
+// `screen -r 1234.dev`, `screen -d -r dev` or `screen -xS dev`
+fn screen_args_name_session(args: &[String], sty: &str) -> bool {
+    let session_name = sty.split_once('.').map_or(sty, |(_, name)| name);
+
+    args.windows(2).any(|pair| {
+        let attaches = pair[0]
+            .strip_prefix('-')
+            .is_some_and(|flags| !flags.starts_with('-') && flags.contains(['r', 'R', 'x', 'S']));
+        attaches && (pair[1] == sty || pair[1] == session_name)
+    })
+}
+
+// Zellij clients don't say which session they're attached to unless it was
+// named on their command line
+fn zellij_client(sys: &System, session: &str, pane_id: Option<&str>) -> Option<Client> {
+    let client = single_client(sys, "zellij", 0, |args| {
+        args.iter().any(|arg| arg == session)
+    })?;
+
+    // Lists each client's focused pane as "terminal_<ZELLIJ_PANE_ID>"
+    let shows_pane = pane_id.is_some_and(|pane_id| {
+        Command::new("zellij")
+            .args(["--session", session, "action", "list-clients"])
+            .output()
+            .is_ok_and(|output| {
+                zellij_pane_focused(&String::from_utf8_lossy(&output.stdout), pane_id)
+            })
+    });
+
+    Some(to_client(client, shows_pane))
+}
+
+fn zellij_pane_focused(output: &str, pane_id: &str) -> bool {
+    let pane = format!("terminal_{}", pane_id);
+    output
+        .lines()
+        .skip(1)
+        .any(|line| line.split_whitespace().nth(1) == Some(pane.as_str()))
+}
+
+// The client process of a multiplexer: the one whose arguments name the
+// session, or the only client running. Servers are excluded.
+fn single_client<'a>(
+    sys: &'a System,
+    name: &str,
+    server_pid: i32,
+    names_session: impl Fn(&[String]) -> bool,
+) -> Option<&'a Process> {
+    let clients: Vec<(&Process, Vec<String>)> = sys
+        .processes()
+        .iter()
+        .filter(|(pid, process)| {
+            pid.as_u32() as i32 != server_pid
+                && process.thread_kind().is_none()
+                && process.name() == name
+        })
+        .map(|(_, process)| {
+            let args = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            (process, args)
+        })
+        .filter(|(_, args)| !args.iter().any(|arg| arg == "--server"))
+        .collect();
+
+    clients
+        .iter()
+        .find(|(_, args)| names_session(args))
+        .or_else(|| (clients.len() == 1).then(|| &clients[0]))
+        .map(|(process, _)| *process)
+}
+
+fn to_client(process: &Process, shows_pane: bool) -> Client {
+    let pid = process.pid().as_u32() as i32;
+    Client {
+        pid,
+        tty: find_terminal_for_process(pid).ok(),
+        shows_pane,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PANES: &str = "100\t$0\t@0\t1\n200\t$0\t@1\t0\n300\t$1\t@2\t1\n";
+
+    #[test]
+    fn finds_pane_by_pid() {
+        assert_eq!(
+            parse_tmux_pane(PANES, 200),
+            Some(TmuxPane {
+                session_id: "$0".to_string(),
+                window_id: "@1".to_string(),
+                active: false,
+            })
+        );
+        assert_eq!(parse_tmux_pane(PANES, 20), None);
+    }
+
+    #[test]
+    fn prefers_client_showing_pane() {
+        let pane = parse_tmux_pane(PANES, 100).unwrap();
+        let clients = "11\t/dev/pts/1\t$0\t@1\t900\n\
+                       12\t/dev/pts/2\t$0\t@0\t100\n\
+                       13\t/dev/pts/3\t$1\t@0\t999\n";
+
+        let client = pick_tmux_client(clients, &pane).unwrap();
+        assert_eq!(client.pid, 12);
+        assert_eq!(client.tty.as_deref(), Some("/dev/pts/2"));
+        assert!(client.shows_pane);
+    }
+
+    #[test]
+    fn falls_back_to_most_active_client_of_session() {
+        let pane = parse_tmux_pane(PANES, 200).unwrap();
+        let clients = "11\t/dev/pts/1\t$0\t@0\t500\n12\t/dev/pts/2\t$0\t@0\t900\n";
+
+        let client = pick_tmux_client(clients, &pane).unwrap();
+        assert_eq!(client.pid, 12);
+        assert!(!client.shows_pane);
+    }
+
+    #[test]
+    fn no_client_for_other_session() {
+        let pane = parse_tmux_pane(PANES, 300).unwrap();
+        assert_eq!(pick_tmux_client("11\t/dev/pts/1\t$0\t@2\t1\n", &pane), None);
+    }
+
+    #[test]
+    fn tmux_socket_from_env_or_server() {
+        let args = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            tmux_socket_args(Some("/tmp/tmux-1000/work,4242,0"), &[]),
+            args("-S /tmp/tmux-1000/work")
+        );
+        assert_eq!(
+            tmux_socket_args(None, &args("tmux -L outer new-session -d")),
+            args("-L outer")
+        );
+        assert_eq!(
+            tmux_socket_args(Some(""), &args("tmux -S /run/t.sock attach")),
+            args("-S /run/t.sock")
+        );
+        assert!(tmux_socket_args(None, &args("tmux: server")).is_empty());
+    }
+
+    #[test]
+    fn screen_session_named_exactly() {
+        let args = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+        assert!(screen_args_name_session(
+            &args("screen -r 1234.dev"),
+            "1234.dev"
+        ));
+        assert!(screen_args_name_session(
+            &args("screen -d -r dev"),
+            "1234.dev"
+        ));
+        assert!(screen_args_name_session(
+            &args("screen -xS dev"),
+            "1234.dev"
+        ));
+        assert!(!screen_args_name_session(
+            &args("screen -r devbox"),
+            "1234.dev"
+        ));
+        assert!(!screen_args_name_session(&args("screen -r 1"), "1234.dev"));
+        assert!(!screen_args_name_session(&args("screen dev"), "1234.dev"));
+    }
+
+    #[test]
+    fn zellij_focused_pane() {
+        let output = "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n\
+                      1         terminal_2     claude\n\
+                      2         plugin_1       N/A\n";
+
+        assert!(zellij_pane_focused(output, "2"));
+        assert!(!zellij_pane_focused(output, "1"));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::cli::HotkeyConfig;
+use crate::context::CaptureContext;
+use crate::logger;
+use crate::process;
+use crate::screenshot::CaptureMode;
+use crate::target::{self, Target};
+use crate::terminal;
+use serde::{Deserialize, Serialize};
+
+/// Sends captures that meet every condition given to `target`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteRule {
+    /// Focused application name contains this (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Focused window title contains this (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<CaptureMode>,
+    /// Hotkey used for the capture, e.g. "ctrl+shift+s"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    pub target: Target,
+}
+
+impl RouteRule {
+    pub fn matches(&self, context: &CaptureContext) -> bool {
+        let contains = |pattern: &Option<String>, value: &Option<String>| match pattern {
+            None => true,
+            Some(pattern) => value
+                .as_deref()
+                .is_some_and(|value| value.to_lowercase().contains(&pattern.to_lowercase())),
+        };
+
+        contains(&self.app, &context.app)
+            && contains(&self.title, &context.window_title)
+            && self.mode.is_none_or(|mode| mode == context.mode)
+            && self.hotkey.as_deref().is_none_or(|hotkey| {
+                context
+                    .hotkey
+                    .as_deref()
+                    .is_some_and(|used| same_hotkey(hotkey, used))
+            })
+    }
+}
+
+/// Picks the session a capture goes to: an explicit target, then the first
+/// routing rule matching the capture, then the pinned session. `None` means
+/// whichever session a backend reaches first.
+pub fn resolve_target(
+    explicit: Option<&Target>,
+    context: Option<&CaptureContext>,
+    rules: &[RouteRule],
+) -> Option<Target> {
+    if let Some(target) = explicit {
+        return Some(target.clone());
+    }
+
+    if let Some(context) = context
+        && let Some(rule) = rules.iter().find(|rule| rule.matches(context))
+    {
+        let sessions = process::find_claude_code_processes().unwrap_or_default();
+        if terminal::select_sessions(sessions, &rule.target).is_ok() {
+            logger::info(&format!("Routing rule matched, sending to {}", rule.target));
+            return Some(rule.target.clone());
+        }
+
+        logger::warning(&format!(
+            "No Claude Code session matches routed target {}, using the default",
+            rule.target
+        ));
+    }
+
+    target::load_pinned_target().map(|pin| Target::Pid(pin.pid))
+}
+
+fn same_hotkey(a: &str, b: &str) -> bool {
+    match (HotkeyConfig::from_spec(a), HotkeyConfig::from_spec(b)) {
+        (Ok(a), Ok(b)) => a.modifiers() == b.modifiers() && a.key() == b.key(),
+        _ => a.eq_ignore_ascii_case(b),
+    }
+}
@@ -1,28 +1,112 @@
 use anyhow::{Ok, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
 use std::process::Command;
 
-pub fn capture() -> Result<String> {
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureMode {
+    /// Drag to select an area (space switches to window selection on macOS)
+    #[default]
+    Region,
+    /// Click a window
+    Window,
+    /// The whole main display
+    Fullscreen,
+}
+
+impl fmt::Display for CaptureMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureMode::Region => write!(f, "region"),
+            CaptureMode::Window => write!(f, "window"),
+            CaptureMode::Fullscreen => write!(f, "fullscreen"),
+        }
+    }
+}
+
+pub fn capture(mode: CaptureMode) -> Result<String> {
     let temp_dir = tempfile::tempdir()?;
     let path = temp_dir.path().join("screenshot.png");
     let path_str = path.to_string_lossy().to_string();
 
+    capture_to(&path, mode)?;
+
+    if !path.exists() {
+        return Err(anyhow::anyhow!("Screenshot was cancelled"));
+    }
+
+    std::mem::forget(temp_dir);
+    Ok(path_str)
+}
+
+#[cfg(target_os = "macos")]
+fn capture_to(path: &Path, mode: CaptureMode) -> Result<()> {
     // macOS screencapture command
     // -i: interactive mode (allows user to select area or window)
+    // -W: start interactive mode in window selection
     // -o: no shadow for window capture
-    let output = Command::new("screencapture")
-        .arg("-i") // Interactive mode - allows selection or window capture with space bar
-        .arg("-o") // No shadow
-        .arg(&path_str)
-        .output()?;
+    let mut command = Command::new("screencapture");
+    match mode {
+        CaptureMode::Region => command.arg("-i"),
+        CaptureMode::Window => command.args(["-i", "-W"]),
+        CaptureMode::Fullscreen => &mut command,
+    };
+
+    let output = command.arg("-o").arg(path).output()?;
 
     if !output.status.success() {
         return Err(anyhow::anyhow!("Screenshot capture failed"));
     }
 
-    if !path.exists() {
-        return Err(anyhow::anyhow!("Screenshot was cancelled"));
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture_to(path: &Path, mode: CaptureMode) -> Result<()> {
+    use crate::process::command_exists;
+
+    if mode == CaptureMode::Fullscreen {
+        let screens = screenshots::Screen::all()?;
+        let screen = screens
+            .iter()
+            .find(|screen| screen.display_info.is_primary)
+            .or(screens.first())
+            .ok_or_else(|| anyhow::anyhow!("No display found"))?;
+
+        screen.capture()?.save(path)?;
+        return Ok(());
     }
 
-    std::mem::forget(temp_dir);
-    Ok(path_str)
+    // Interactive selection needs an external tool: maim on X11, or grim and
+    // slurp on wlroots compositors. Clicking instead of dragging selects a
+    // window with either.
+    let output = if command_exists("maim") {
+        Command::new("maim").arg("-s").arg(path).output()?
+    } else if command_exists("grim") && command_exists("slurp") {
+        let selection = Command::new("slurp").output()?;
+        if !selection.status.success() {
+            return Err(anyhow::anyhow!("Screenshot was cancelled"));
+        }
+        let geometry = String::from_utf8_lossy(&selection.stdout)
+            .trim()
+            .to_string();
+
+        Command::new("grim")
+            .args(["-g", &geometry])
+            .arg(path)
+            .output()?
+    } else {
+        return Err(anyhow::anyhow!(
+            "Interactive capture needs maim (X11) or grim and slurp (Wayland)"
+        ));
+    };
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("Screenshot capture failed"));
+    }
+
+    Ok(())
 }
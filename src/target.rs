@@ -0,0 +1,216 @@
+use crate::logger;
+use crate::process::ClaudeSession;
+use anyhow::Result;
+use dirs::home_dir;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Which Claude Code session to deliver to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Target {
+    Pid(i32),
+    Tty(String),
+    Cwd(PathBuf),
+    /// Terminal session title, or the name of the session's working directory
+    Name(String),
+    /// Every session, delivered to one by one
+    All,
+    /// Every session matching any of these, delivered to one by one
+    List(Vec<Target>),
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.trim().eq_ignore_ascii_case("all") {
+            return Ok(Target::All);
+        }
+        if input.contains(',') {
+            return input
+                .split(',')
+                .filter(|part| !part.trim().is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<Target>, _>>()
+                .map(Target::List);
+        }
+
+        let (kind, value) = input
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid target '{}', expected <kind>:<value>", input))?;
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("Missing value in target '{}'", input));
+        }
+
+        match kind.trim().to_lowercase().as_str() {
+            "pid" => value
+                .parse()
+                .map(Target::Pid)
+                .map_err(|_| format!("Invalid pid: {}", value)),
+            "tty" => Ok(Target::Tty(if value.starts_with("/dev/") {
+                value.to_string()
+            } else {
+                format!("/dev/{}", value)
+            })),
+            "cwd" => Ok(Target::Cwd(expand_home(value))),
+            "name" => Ok(Target::Name(value.to_string())),
+            _ => Err(format!(
+                "Unknown target kind '{}', expected pid, tty, cwd or name",
+                kind
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Target {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.to_string()
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Pid(pid) => write!(f, "pid:{}", pid),
+            Target::Tty(tty) => write!(f, "tty:{}", tty),
+            Target::Cwd(cwd) => write!(f, "cwd:{}", cwd.display()),
+            Target::Name(name) => write!(f, "name:{}", name),
+            Target::All => write!(f, "all"),
+            Target::List(targets) => {
+                let targets: Vec<String> = targets.iter().map(Target::to_string).collect();
+                write!(f, "{}", targets.join(","))
+            }
+        }
+    }
+}
+
+impl Target {
+    /// Whether `session` is the one this target refers to. `title` is the
+    /// terminal's title for the session, when the backend knows it.
+    pub fn matches(&self, session: &ClaudeSession, title: Option<&str>) -> bool {
+        match self {
+            Target::Pid(pid) => session.pid == *pid,
+            Target::Tty(tty) => session.tty.as_deref() == Some(tty.as_str()),
+            Target::Cwd(cwd) => session.cwd.as_deref().is_some_and(|own| same_dir(own, cwd)),
+            Target::Name(name) => {
+                title.is_some_and(|title| title.eq_ignore_ascii_case(name))
+                    || session
+                        .cwd
+                        .as_deref()
+                        .and_then(Path::file_name)
+                        .and_then(|dir| dir.to_str())
+                        .is_some_and(|dir| dir.eq_ignore_ascii_case(name))
+            }
+            Target::All => true,
+            Target::List(targets) => targets.iter().any(|t| t.matches(session, title)),
+        }
+    }
+
+    /// Whether the capture goes to every matching session rather than one
+    pub fn is_broadcast(&self) -> bool {
+        matches!(self, Target::All | Target::List(_))
+    }
+
+    /// Whether matching needs the terminal's session titles
+    pub fn needs_titles(&self) -> bool {
+        match self {
+            Target::Name(_) => true,
+            Target::List(targets) => targets.iter().any(Target::needs_titles),
+            _ => false,
+        }
+    }
+}
+
+/// A target pinned with `paparazzi target set`, bound to the session it
+/// resolved to so the pin expires when that session exits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedTarget {
+    pub target: String,
+    pub pid: i32,
+    pub start_time: u64,
+}
+
+fn get_pin_path() -> PathBuf {
+    home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".paparazzi")
+        .join("target.json")
+}
+
+pub fn save_pinned_target(pin: &PinnedTarget) -> Result<()> {
+    let pin_path = get_pin_path();
+
+    if let Some(parent) = pin_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&pin_path, serde_json::to_string_pretty(pin)?)?;
+    Ok(())
+}
+
+pub fn clear_pinned_target() -> Result<()> {
+    let pin_path = get_pin_path();
+    if pin_path.exists() {
+        fs::remove_file(pin_path)?;
+    }
+    Ok(())
+}
+
+/// The pinned target, as long as the session it was pinned to is still running.
+pub fn load_pinned_target() -> Option<PinnedTarget> {
+    let contents = fs::read_to_string(get_pin_path()).ok()?;
+    let pin = serde_json::from_str::<PinnedTarget>(&contents).ok()?;
+
+    if !is_running(pin.pid, pin.start_time) {
+        logger::info(&format!(
+            "Pinned session {} (PID {}) has exited, unpinning",
+            pin.target, pin.pid
+        ));
+        let _ = clear_pinned_target();
+        return None;
+    }
+
+    Some(pin)
+}
+
+// The pid alone could have been reused by another process since pinning
+fn is_running(pid: i32, start_time: u64) -> bool {
+    let pid = Pid::from(pid as usize);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+
+    sys.process(pid)
+        .is_some_and(|process| process.start_time() == start_time)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
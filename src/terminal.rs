@@ -1,404 +1,337 @@
 use anyhow::Result;
 
-#[cfg(target_os = "macos")]
+use crate::config::{self, BackendsConfig};
 use crate::logger;
+use crate::process::{self, ClaudeSession};
+use crate::target::Target;
 #[cfg(target_os = "macos")]
-use std::fs;
-#[cfg(target_os = "macos")]
-use std::process::Command;
-#[cfg(target_os = "macos")]
-use tempfile::NamedTempFile;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
 
-// Embedded AppleScript content
+#[cfg(target_os = "linux")]
+mod konsole;
 #[cfg(target_os = "macos")]
-const ITERM2_SCRIPT: &str = r#"on run argv
-    set messageText to item 1 of argv
-
-    tell application "iTerm"
-        set foundSession to false
-
-        -- Loop through all windows
-        repeat with w in windows
-            -- Loop through all tabs in the window
-            repeat with t in tabs of w
-                -- Loop through all sessions in the tab
-                repeat with s in sessions of t
-                    -- Get the session content
-                    set sessionText to contents of s
-
-                    -- Check if this session contains Claude Code indicators
-                    if sessionText contains "claude" or sessionText contains "Claude" then
-                        -- Switch to this window
-                        select w
-                        -- Switch to this tab
-                        select t
-                        -- Switch to this session
-                        select s
-                        -- Send the text
-                        tell s to write text messageText
-                        set foundSession to true
-                        exit repeat
-                    end if
-                end repeat
-                if foundSession then exit repeat
-            end repeat
-            if foundSession then exit repeat
-        end repeat
-
-        if foundSession then
-            activate
-            return "true"
-        else
-            return "false"
-        end if
-    end tell
-end run"#;
+mod macos;
+mod nvim;
+#[cfg(target_os = "linux")]
+mod x11;
 
-#[cfg(target_os = "macos")]
-const TERMINAL_SCRIPT: &str = r#"on run argv
-    set messageText to item 1 of argv
-
-    tell application "Terminal"
-        set foundTab to false
-
-        -- Loop through all windows
-        repeat with w in windows
-            -- Loop through all tabs in the window
-            repeat with t in tabs of w
-                -- Get the tab's processes
-                set tabProcesses to processes of t
-
-                -- Check if any process contains "claude"
-                repeat with p in tabProcesses
-                    if p contains "claude" or p contains "Claude" then
-                        -- Switch to this window and tab
-                        set frontmost of w to true
-                        set selected of t to true
-                        activate
-
-                        -- Send the text
-                        do script messageText in t
-                        set foundTab to true
-                        exit repeat
-                    end if
-                end repeat
-                if foundTab then exit repeat
-            end repeat
-            if foundTab then exit repeat
-        end repeat
-
-        return foundTab
-    end tell
-end run"#;
+/// A way of getting text into the terminal a Claude Code process runs in.
+pub trait TerminalBackend {
+    /// Name used in the config and by `paparazzi backends`
+    fn name(&self) -> &'static str;
 
-#[cfg(target_os = "macos")]
-const GHOSTTY_SCRIPT: &str = r#"on run argv
-    set messageText to item 1 of argv
-
-    -- First, copy the message to clipboard using pbcopy
-    set the clipboard to messageText
-
-    tell application "System Events"
-        tell process "Ghostty"
-            set frontmost to true
-
-            -- Look for windows
-            set windowList to windows
-            if (count of windowList) > 0 then
-                -- Focus the first/current window (assuming it's the active one)
-                set targetWindow to item 1 of windowList
-                perform action "AXRaise" of targetWindow
-                set focused of targetWindow to true
-
-                -- Wait a moment for focus
-                delay 0.3
-
-                -- Paste the content using Cmd+V
-                key code 9 using command down
-
-                -- Press return
-                delay 0.1
-                key code 36
-                return "true"
-            end if
-        end tell
-    end tell
-    return "false"
-end run"#;
+    fn description(&self) -> &'static str;
 
-#[cfg(target_os = "macos")]
-const TERMINAL_TTY_SCRIPT: &str = r#"on run argv
-    set ttyPath to item 1 of argv
-    set messageText to item 2 of argv
-
-    tell application "Terminal"
-        set foundTab to false
-
-        -- Loop through all windows
-        repeat with w in windows
-            -- Loop through all tabs in the window
-            repeat with t in tabs of w
-                -- Get the tab's tty
-                set tabTty to tty of t
-
-                -- Check if this matches our target tty
-                if tabTty is equal to ttyPath then
-                    -- Switch to this window and tab
-                    set frontmost of w to true
-                    set selected of t to true
-                    activate
-
-                    -- Send the text
-                    do script messageText in t
-                    set foundTab to true
-                    exit repeat
-                end if
-            end repeat
-            if foundTab then exit repeat
-        end repeat
-
-        return foundTab
-    end tell
-end run"#;
-
-// Helper function to create temporary AppleScript file and return its path
-#[cfg(target_os = "macos")]
-fn create_temp_script(script_content: &str) -> Result<NamedTempFile> {
-    let temp_file = NamedTempFile::new()?;
-    fs::write(temp_file.path(), script_content)?;
-    Ok(temp_file)
-}
+    /// Whether the backend can be used on this machine right now
+    fn detect(&self) -> bool;
 
-#[cfg(target_os = "macos")]
-pub fn send_to_claude_code_terminal(message: &str) -> Result<()> {
-    let pids = find_claude_code_processes()?;
-    logger::info(&format!(
-        "Found {} Claude Code processes: {:?}",
-        pids.len(),
-        pids
-    ));
+    /// Sessions the backend can deliver to. `claude_pids` is a hint for
+    /// backends that discover their sessions through the Claude processes.
+    fn list_sessions(&self, claude_pids: &[i32]) -> Result<Vec<BackendSession>>;
 
-    if send_to_iterm2_claude_tab(message).is_ok() {
-        logger::success("Sent to Claude Code via iTerm2");
-        return Ok(());
-    }
-
-    if send_to_terminal_app_claude_tab(message).is_ok() {
-        logger::success("Sent to Claude Code via Terminal.app");
-        return Ok(());
-    }
-
-    if send_to_ghostty_claude_tab(message).is_ok() {
-        logger::success("Sent to Claude Code via Ghostty");
-        return Ok(());
-    }
-
-    for pid in pids {
-        println!("\nTrying PID: {}", pid);
-
-        if let Ok(tty) = find_terminal_for_process(pid) {
-            println!("TTY: {}", tty);
-
-            if send_to_terminal_app_by_tty(&tty, message).is_ok() {
-                logger::success("Sent via Terminal.app (TTY method)");
-                return Ok(());
-            }
-        }
-    }
+    /// Sends `message` to `session` and presses Enter
+    fn deliver(&self, session: &BackendSession, message: &str) -> Result<()>;
+}
 
-    Err(anyhow::anyhow!(
-        "Could not send to Claude Code terminal. Make sure Claude Code is running in a terminal."
-    ))
+/// A terminal session (tab, pane, buffer or window) as seen by a backend.
+#[derive(Debug, Clone)]
+pub struct BackendSession {
+    /// Backend-specific locator passed back to `deliver`
+    pub id: String,
+    /// Process the session runs: its shell, job or terminal emulator
+    pub pid: Option<i32>,
+    pub tty: Option<String>,
+    pub title: Option<String>,
 }
 
-#[cfg(target_os = "macos")]
-fn find_claude_code_processes() -> Result<Vec<i32>> {
-    // Look for processes with "claude" in the command line
-    // This should catch both "claude" and "claude-code" commands
-    let output = Command::new("sh")
-        .arg("-c")
-        .arg("ps aux | grep -E '(claude|claude-code)' | grep -v grep | awk '{print $2}'")
-        .output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-
-    let pids: Vec<i32> = stdout
-        .lines()
-        .filter_map(|line| line.trim().parse::<i32>().ok())
-        .collect();
+impl BackendSession {
+    /// Whether the Claude process runs in this session, directly or in a
+    /// multiplexer pane the session is showing
+    pub fn hosts(&self, claude: &ClaudeSession) -> bool {
+        if let Some(own) = self.tty.as_deref()
+            && (claude.tty.as_deref() == Some(own)
+                || claude.chain.outer_ttys.iter().any(|tty| tty == own))
+        {
+            return true;
+        }
 
-    if pids.is_empty() {
-        return Err(anyhow::anyhow!(
-            "No Claude Code processes found. Please make sure Claude Code is running."
-        ));
+        self.pid.is_some_and(|own| {
+            own == claude.pid || claude.has_ancestor(own) || claude.chain.outer_pids.contains(&own)
+        })
     }
-
-    Ok(pids)
 }
 
-#[cfg(target_os = "macos")]
-fn find_terminal_for_process(pid: i32) -> Result<String> {
-    let output = Command::new("ps")
-        .args(["-p", &pid.to_string(), "-o", "tty="])
-        .output()?;
-
-    let tty = String::from_utf8_lossy(&output.stdout).trim().to_string();
+/// Every backend supported on this platform, in the default delivery order.
+pub fn all_backends() -> Vec<Box<dyn TerminalBackend>> {
+    let mut backends: Vec<Box<dyn TerminalBackend>> = vec![Box::new(nvim::Nvim)];
 
-    if tty.is_empty() || tty == "??" {
-        return Err(anyhow::anyhow!("No TTY found for process"));
+    #[cfg(target_os = "macos")]
+    {
+        backends.push(Box::new(macos::Iterm2));
+        backends.push(Box::new(macos::TerminalApp));
+        backends.push(Box::new(macos::Ghostty));
     }
 
-    let full_tty = if tty.starts_with("/dev/") {
-        tty
-    } else {
-        format!("/dev/{}", tty)
-    };
+    #[cfg(target_os = "linux")]
+    {
+        backends.push(Box::new(konsole::Konsole));
+        backends.push(Box::new(x11::X11));
+    }
 
-    Ok(full_tty)
+    backends
 }
 
-// iTerm2
-#[cfg(target_os = "macos")]
-fn send_to_iterm2_claude_tab(message: &str) -> Result<()> {
-    let script_file = create_temp_script(ITERM2_SCRIPT)?;
-
-    let output = Command::new("osascript")
-        .arg(script_file.path())
-        .arg(message)
-        .output()?;
+/// Enabled backends, with the configured ones first in their configured order.
+pub fn configured_backends(config: &BackendsConfig) -> Vec<Box<dyn TerminalBackend>> {
+    let mut remaining = all_backends();
+    let mut ordered = Vec::new();
 
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!("iTerm2 not available or error: {}", error));
+    for name in &config.order {
+        if let Some(index) = remaining.iter().position(|b| b.name() == name) {
+            ordered.push(remaining.remove(index));
+        }
     }
+    ordered.extend(remaining);
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let result = stdout.trim();
+    ordered.retain(|b| !config.disabled.iter().any(|name| name == b.name()));
+    ordered
+}
 
-    if result == "false" {
-        return Err(anyhow::anyhow!("Claude Code tab not found in iTerm2"));
+/// Sends `message` to the Claude Code session `target` refers to, or to the
+/// first session a backend can reach when there's no target.
+pub fn send_to_claude_code_terminal(message: &str, target: Option<&Target>) -> Result<()> {
+    let mut sessions = process::find_claude_code_processes()?;
+    if let Some(target) = target {
+        sessions = select_sessions(sessions, target)?;
+        logger::info(&format!("Sending to target {}", target));
+    }
+    logger::info(&format!("Found {} Claude Code processes", sessions.len()));
+    for session in &sessions {
+        logger::info(&describe_session(session));
+    }
+    let pids: Vec<i32> = sessions.iter().map(|session| session.pid).collect();
+
+    for backend in configured_backends(&config::load_config().backends) {
+        let Some(backend_sessions) = available_sessions(backend.as_ref(), &pids) else {
+            continue;
+        };
+
+        let Some(session) = backend_sessions
+            .iter()
+            .find(|session| sessions.iter().any(|claude| session.hosts(claude)))
+        else {
+            continue;
+        };
+
+        match backend.deliver(session, message) {
+            Ok(()) => {
+                logger::success(&format!(
+                    "Sent to Claude Code via {} ({})",
+                    backend.name(),
+                    session.title.as_deref().unwrap_or(&session.id)
+                ));
+                return Ok(());
+            }
+            Err(e) => logger::warning(&format!("{}: {}", backend.name(), e)),
+        }
     }
 
-    Ok(())
+    Err(anyhow::anyhow!(
+        "Could not send to Claude Code terminal. Make sure Claude Code is running in a supported terminal."
+    ))
 }
 
-// Terminal.app
-#[cfg(target_os = "macos")]
-fn send_to_terminal_app_claude_tab(message: &str) -> Result<()> {
-    let script_file = create_temp_script(TERMINAL_SCRIPT)?;
-
-    let output = Command::new("osascript")
-        .arg(script_file.path())
-        .arg(message)
-        .output()?;
+/// Outcome of delivering to one Claude session of a broadcast.
+pub struct Delivery {
+    pub session: ClaudeSession,
+    /// Backend and terminal session delivered through, or why delivery failed
+    pub result: Result<String>,
+}
 
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!("Terminal.app error: {}", error));
+/// Sends `message` to every Claude Code session `target` refers to, one
+/// after another. A failed session doesn't stop delivery to the others.
+pub fn broadcast_to_claude_code_sessions(message: &str, target: &Target) -> Result<Vec<Delivery>> {
+    let sessions = select_sessions(process::find_claude_code_processes()?, target)?;
+    logger::info(&format!(
+        "Broadcasting to {} Claude Code sessions matching {}",
+        sessions.len(),
+        target
+    ));
+    for session in &sessions {
+        logger::info(&describe_session(session));
     }
+    let pids: Vec<i32> = sessions.iter().map(|session| session.pid).collect();
+
+    // List each backend's sessions once for the whole broadcast
+    let backends: Vec<(Box<dyn TerminalBackend>, Vec<BackendSession>)> =
+        configured_backends(&config::load_config().backends)
+            .into_iter()
+            .filter_map(|backend| {
+                let backend_sessions = available_sessions(backend.as_ref(), &pids)?;
+                Some((backend, backend_sessions))
+            })
+            .collect();
+
+    let deliveries = sessions
+        .into_iter()
+        .map(|session| {
+            let result = deliver_to_session(&backends, &session, message);
+            match &result {
+                Ok(via) => logger::success(&format!("PID {}: sent via {}", session.pid, via)),
+                Err(e) => logger::error(&format!("PID {}: {}", session.pid, e)),
+            }
+            Delivery { session, result }
+        })
+        .collect();
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let result = stdout.trim();
+    Ok(deliveries)
+}
 
-    if result == "false" {
-        return Err(anyhow::anyhow!("Claude Code tab not found in Terminal.app"));
+// Delivers through the first backend hosting `claude` that succeeds
+fn deliver_to_session(
+    backends: &[(Box<dyn TerminalBackend>, Vec<BackendSession>)],
+    claude: &ClaudeSession,
+    message: &str,
+) -> Result<String> {
+    let mut last_error = None;
+
+    for (backend, backend_sessions) in backends {
+        let Some(session) = backend_sessions.iter().find(|s| s.hosts(claude)) else {
+            continue;
+        };
+
+        match backend.deliver(session, message) {
+            Ok(()) => {
+                return Ok(format!(
+                    "{} ({})",
+                    backend.name(),
+                    session.title.as_deref().unwrap_or(&session.id)
+                ));
+            }
+            Err(e) => {
+                logger::warning(&format!("{}: {}", backend.name(), e));
+                last_error = Some(e);
+            }
+        }
     }
 
-    Ok(())
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No terminal backend can reach this session")))
 }
 
-// Ghostty
-#[cfg(target_os = "macos")]
-fn send_to_ghostty_claude_tab(message: &str) -> Result<()> {
-    // Get Claude Code PIDs to identify the right terminal
-    let claude_pids = find_claude_code_processes()?;
-
-    // Check if any of the Claude processes are running in Ghostty
-    for pid in &claude_pids {
-        if let Ok(terminal_name) = get_terminal_name_for_process(*pid)
-            && terminal_name.to_lowercase().contains("ghostty")
-        {
-            // Found Claude running in Ghostty, proceed with the script
-            let script_file = create_temp_script(GHOSTTY_SCRIPT)?;
-
-            let output = Command::new("osascript")
-                .arg(script_file.path())
-                .arg(message)
-                .output()?;
+/// For each Claude session, the backend that would deliver to it and the
+/// backend's view of the session.
+pub fn route_sessions(sessions: &[ClaudeSession]) -> Vec<Option<(&'static str, BackendSession)>> {
+    let pids: Vec<i32> = sessions.iter().map(|session| session.pid).collect();
+    let mut routes = vec![None; sessions.len()];
 
-            if !output.status.success() {
-                let error = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow::anyhow!("Ghostty automation error: {}", error));
-            }
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let result = stdout.trim();
-
-            if result == "true" {
-                return Ok(());
+    for backend in configured_backends(&config::load_config().backends) {
+        if routes.iter().all(Option::is_some) {
+            break;
+        }
+        let Some(backend_sessions) = available_sessions(backend.as_ref(), &pids) else {
+            continue;
+        };
+
+        for (claude, route) in sessions.iter().zip(routes.iter_mut()) {
+            if route.is_none()
+                && let Some(session) = backend_sessions.iter().find(|s| s.hosts(claude))
+            {
+                *route = Some((backend.name(), session.clone()));
             }
         }
     }
 
-    Err(anyhow::anyhow!("Claude Code not found running in Ghostty"))
+    routes
 }
 
-// Helper function to get terminal name for a process
-#[cfg(target_os = "macos")]
-fn get_terminal_name_for_process(pid: i32) -> Result<String> {
-    // Get the parent process ID to find the terminal
-    let output = Command::new("ps")
-        .args(["-p", &pid.to_string(), "-o", "ppid="])
-        .output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let ppid_str = stdout.trim();
-    let ppid: i32 = ppid_str.parse()?;
-
-    // Get the command name of the parent process (should be the terminal)
-    let output = Command::new("ps")
-        .args(["-p", &ppid.to_string(), "-o", "comm="])
-        .output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let terminal_name = stdout.trim().to_string();
-
-    if terminal_name.is_empty() {
-        return Err(anyhow::anyhow!("Could not determine terminal name"));
+/// The Claude sessions `target` refers to
+pub fn select_sessions(
+    sessions: Vec<ClaudeSession>,
+    target: &Target,
+) -> Result<Vec<ClaudeSession>> {
+    // Only name: targets need the terminal's session titles, which are slow to list
+    let routes = if target.needs_titles() {
+        route_sessions(&sessions)
+    } else {
+        vec![None; sessions.len()]
+    };
+
+    let selected: Vec<ClaudeSession> = sessions
+        .into_iter()
+        .zip(routes)
+        .filter(|(session, route)| {
+            let title = route
+                .as_ref()
+                .and_then(|(_, backend_session)| backend_session.title.as_deref());
+            target.matches(session, title)
+        })
+        .map(|(session, _)| session)
+        .collect();
+
+    if selected.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No Claude Code session matches target {}",
+            target
+        ));
     }
 
-    Ok(terminal_name)
+    Ok(selected)
 }
 
-// Terminal.app
-#[cfg(target_os = "macos")]
-fn send_to_terminal_app_by_tty(tty: &str, message: &str) -> Result<()> {
-    let script_file = create_temp_script(TERMINAL_TTY_SCRIPT)?;
-
-    let output = Command::new("osascript")
-        .arg(script_file.path())
-        .arg(tty)
-        .arg(message)
-        .output()?;
-
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!("Terminal.app TTY error: {}", error));
+// Sessions a backend can deliver to right now, or None when it's unavailable
+fn available_sessions(
+    backend: &dyn TerminalBackend,
+    claude_pids: &[i32],
+) -> Option<Vec<BackendSession>> {
+    if !backend.detect() {
+        return None;
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let result = stdout.trim();
-
-    if result == "false" {
-        return Err(anyhow::anyhow!("TTY not found in Terminal.app"));
+    match backend.list_sessions(claude_pids) {
+        Ok(sessions) => Some(sessions),
+        Err(e) => {
+            logger::warning(&format!("{}: {}", backend.name(), e));
+            None
+        }
     }
+}
 
-    Ok(())
+fn describe_session(session: &ClaudeSession) -> String {
+    let started = chrono::DateTime::from_timestamp(session.start_time as i64, 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    format!(
+        "  PID {} on {} in {} (started {}, in {})",
+        session.pid,
+        session.tty.as_deref().unwrap_or("no tty"),
+        session
+            .cwd
+            .as_ref()
+            .map(|cwd| cwd.display().to_string())
+            .unwrap_or_else(|| "?".to_string()),
+        started,
+        if session.chain.layers.is_empty() {
+            "an unknown terminal".to_string()
+        } else {
+            session.chain.to_string()
+        }
+    )
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn send_to_claude_code_terminal(_message: &str) -> Result<()> {
-    Err(anyhow::anyhow!(
-        "Terminal integration is only supported on macOS"
-    ))
+// Pids of running processes with the given name (case-insensitive)
+#[cfg(target_os = "macos")]
+fn pids_by_name(name: &str) -> Vec<i32> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+
+    sys.processes()
+        .iter()
+        .filter(|(_, process)| process.name().eq_ignore_ascii_case(name))
+        .map(|(pid, _)| pid.as_u32() as i32)
+        .collect()
 }
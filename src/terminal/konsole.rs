@@ -0,0 +1,110 @@
+use super::{BackendSession, TerminalBackend};
+use anyhow::Result;
+use dbus::blocking::stdintf::org_freedesktop_dbus::Introspectable;
+use dbus::blocking::{Connection, Proxy};
+use std::time::Duration;
+
+const DBUS_TIMEOUT: Duration = Duration::from_secs(2);
+const SESSION_INTERFACE: &str = "org.kde.konsole.Session";
+
+// Every app embedding konsolepart exports its sessions under /Sessions/<n> on
+// its own bus name: Konsole itself (org.kde.konsole or org.kde.konsole-<pid>),
+// Yakuake, and the terminal panels of Kate and Dolphin.
+//
+// GNOME Terminal, Tilix and friends have no D-Bus API for injecting text, so
+// they are left to the keystroke fallback.
+const SERVICE_PREFIXES: &[&str] = &[
+    "org.kde.konsole",
+    "org.kde.yakuake",
+    "org.kde.kate",
+    "org.kde.dolphin",
+];
+
+pub struct Konsole;
+
+impl TerminalBackend for Konsole {
+    fn name(&self) -> &'static str {
+        "konsole"
+    }
+
+    fn description(&self) -> &'static str {
+        "Konsole and konsolepart sessions via D-Bus"
+    }
+
+    fn detect(&self) -> bool {
+        Connection::new_session()
+            .and_then(|conn| konsole_services(&conn))
+            .is_ok_and(|services| !services.is_empty())
+    }
+
+    // The session's shell is one of Claude's ancestors, or Claude itself when
+    // it was started as the session's program
+    fn list_sessions(&self, _claude_pids: &[i32]) -> Result<Vec<BackendSession>> {
+        let conn = Connection::new_session()?;
+        let mut sessions = Vec::new();
+
+        for service in konsole_services(&conn)? {
+            let root = conn.with_proxy(service.as_str(), "/Sessions", DBUS_TIMEOUT);
+            let Ok(xml) = root.introspect() else {
+                continue;
+            };
+
+            for node in child_nodes(&xml) {
+                let path = format!("/Sessions/{}", node);
+                let proxy = conn.with_proxy(service.as_str(), path.as_str(), DBUS_TIMEOUT);
+
+                sessions.push(BackendSession {
+                    pid: call_pid(&proxy, "processId"),
+                    id: format!("{} {}", service, path),
+                    tty: None,
+                    title: None,
+                });
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    fn deliver(&self, session: &BackendSession, message: &str) -> Result<()> {
+        let (service, path) = session
+            .id
+            .split_once(' ')
+            .ok_or_else(|| anyhow::anyhow!("Invalid Konsole session: {}", session.id))?;
+
+        let conn = Connection::new_session()?;
+        let proxy = conn.with_proxy(service, path, DBUS_TIMEOUT);
+        proxy.method_call::<(), _, _, _>(SESSION_INTERFACE, "sendText", (message,))?;
+        // Send Enter separately so Claude doesn't treat it as part of a paste
+        std::thread::sleep(Duration::from_millis(50));
+        proxy.method_call::<(), _, _, _>(SESSION_INTERFACE, "sendText", ("\r",))?;
+
+        Ok(())
+    }
+}
+
+fn konsole_services(conn: &Connection) -> Result<Vec<String>, dbus::Error> {
+    let bus = conn.with_proxy("org.freedesktop.DBus", "/", DBUS_TIMEOUT);
+    let (names,): (Vec<String>,) = bus.method_call("org.freedesktop.DBus", "ListNames", ())?;
+
+    Ok(names
+        .into_iter()
+        .filter(|name| SERVICE_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .collect())
+}
+
+fn call_pid(proxy: &Proxy<'_, &Connection>, method: &str) -> Option<i32> {
+    proxy
+        .method_call::<(i32,), _, _, _>(SESSION_INTERFACE, method, ())
+        .ok()
+        .map(|(pid,)| pid)
+        .filter(|pid| *pid > 0)
+}
+
+// Pulls `<node name="..."/>` children out of introspection XML.
+fn child_nodes(xml: &str) -> Vec<String> {
+    xml.split("<node name=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .map(str::to_string)
+        .collect()
+}
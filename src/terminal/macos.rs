@@ -0,0 +1,295 @@
+use super::{BackendSession, TerminalBackend};
+use anyhow::Result;
+use std::fs;
+use std::process::Command;
+use tempfile::NamedTempFile;
+
+// Embedded AppleScript content
+const ITERM2_LIST_SCRIPT: &str = r#"on run argv
+    set output to ""
+
+    tell application "iTerm"
+        -- Loop through all windows
+        repeat with w in windows
+            -- Loop through all tabs in the window
+            repeat with t in tabs of w
+                -- Loop through all sessions in the tab
+                repeat with s in sessions of t
+                    set output to output & (id of s) & tab & (tty of s) & tab & (name of s) & linefeed
+                end repeat
+            end repeat
+        end repeat
+    end tell
+
+    return output
+end run"#;
+
+const ITERM2_SCRIPT: &str = r#"on run argv
+    set sessionId to item 1 of argv
+    set messageText to item 2 of argv
+
+    tell application "iTerm"
+        -- Loop through all windows
+        repeat with w in windows
+            -- Loop through all tabs in the window
+            repeat with t in tabs of w
+                -- Loop through all sessions in the tab
+                repeat with s in sessions of t
+                    if (id of s) is equal to sessionId then
+                        -- Switch to this window
+                        select w
+                        -- Switch to this tab
+                        select t
+                        -- Switch to this session
+                        select s
+                        -- Send the text
+                        tell s to write text messageText
+                        activate
+                        return "true"
+                    end if
+                end repeat
+            end repeat
+        end repeat
+
+        return "false"
+    end tell
+end run"#;
+
+const TERMINAL_LIST_SCRIPT: &str = r#"on run argv
+    set output to ""
+
+    tell application "Terminal"
+        -- Loop through all windows
+        repeat with w in windows
+            -- Loop through all tabs in the window
+            repeat with t in tabs of w
+                set output to output & (tty of t) & tab & (tty of t) & tab & (custom title of t) & linefeed
+            end repeat
+        end repeat
+    end tell
+
+    return output
+end run"#;
+
+const TERMINAL_TTY_SCRIPT: &str = r#"on run argv
+    set ttyPath to item 1 of argv
+    set messageText to item 2 of argv
+
+    tell application "Terminal"
+        set foundTab to false
+
+        -- Loop through all windows
+        repeat with w in windows
+            -- Loop through all tabs in the window
+            repeat with t in tabs of w
+                -- Get the tab's tty
+                set tabTty to tty of t
+
+                -- Check if this matches our target tty
+                if tabTty is equal to ttyPath then
+                    -- Switch to this window and tab
+                    set frontmost of w to true
+                    set selected of t to true
+                    activate
+
+                    -- Send the text
+                    do script messageText in t
+                    set foundTab to true
+                    exit repeat
+                end if
+            end repeat
+            if foundTab then exit repeat
+        end repeat
+
+        return foundTab
+    end tell
+end run"#;
+
+const GHOSTTY_SCRIPT: &str = r#"on run argv
+    set messageText to item 1 of argv
+
+    -- First, copy the message to clipboard using pbcopy
+    set the clipboard to messageText
+
+    tell application "System Events"
+        tell process "Ghostty"
+            set frontmost to true
+
+            -- Look for windows
+            set windowList to windows
+            if (count of windowList) > 0 then
+                -- Focus the first/current window (assuming it's the active one)
+                set targetWindow to item 1 of windowList
+                perform action "AXRaise" of targetWindow
+                set focused of targetWindow to true
+
+                -- Wait a moment for focus
+                delay 0.3
+
+                -- Paste the content using Cmd+V
+                key code 9 using command down
+
+                -- Press return
+                delay 0.1
+                key code 36
+                return "true"
+            end if
+        end tell
+    end tell
+    return "false"
+end run"#;
+
+// Helper function to create temporary AppleScript file and return its path
+fn create_temp_script(script_content: &str) -> Result<NamedTempFile> {
+    let temp_file = NamedTempFile::new()?;
+    fs::write(temp_file.path(), script_content)?;
+    Ok(temp_file)
+}
+
+fn run_script(script: &str, args: &[&str]) -> Result<String> {
+    let script_file = create_temp_script(script)?;
+
+    let output = Command::new("osascript")
+        .arg(script_file.path())
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("{}", error.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Parses "id<TAB>tty<TAB>title" lines printed by the list scripts
+fn parse_session_list(output: &str) -> Vec<BackendSession> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let id = fields.next()?.trim();
+            if id.is_empty() {
+                return None;
+            }
+            let tty = fields.next().map(str::trim).filter(|t| !t.is_empty());
+            let title = fields.next().map(str::trim).filter(|t| !t.is_empty());
+
+            Some(BackendSession {
+                id: id.to_string(),
+                pid: None,
+                tty: tty.map(str::to_string),
+                title: title.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+pub struct Iterm2;
+
+impl TerminalBackend for Iterm2 {
+    fn name(&self) -> &'static str {
+        "iterm2"
+    }
+
+    fn description(&self) -> &'static str {
+        "iTerm2 sessions via AppleScript"
+    }
+
+    fn detect(&self) -> bool {
+        // Only talk to iTerm2 when it's already running, otherwise
+        // `tell application` would launch it
+        !super::pids_by_name("iTerm2").is_empty()
+    }
+
+    fn list_sessions(&self, _claude_pids: &[i32]) -> Result<Vec<BackendSession>> {
+        let output = run_script(ITERM2_LIST_SCRIPT, &[])
+            .map_err(|e| anyhow::anyhow!("iTerm2 not available or error: {}", e))?;
+        Ok(parse_session_list(&output))
+    }
+
+    fn deliver(&self, session: &BackendSession, message: &str) -> Result<()> {
+        let result = run_script(ITERM2_SCRIPT, &[&session.id, message])
+            .map_err(|e| anyhow::anyhow!("iTerm2 not available or error: {}", e))?;
+
+        if result == "false" {
+            return Err(anyhow::anyhow!("Claude Code session not found in iTerm2"));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct TerminalApp;
+
+impl TerminalBackend for TerminalApp {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn description(&self) -> &'static str {
+        "Terminal.app tabs via AppleScript"
+    }
+
+    fn detect(&self) -> bool {
+        !super::pids_by_name("Terminal").is_empty()
+    }
+
+    fn list_sessions(&self, _claude_pids: &[i32]) -> Result<Vec<BackendSession>> {
+        let output = run_script(TERMINAL_LIST_SCRIPT, &[])
+            .map_err(|e| anyhow::anyhow!("Terminal.app error: {}", e))?;
+        Ok(parse_session_list(&output))
+    }
+
+    fn deliver(&self, session: &BackendSession, message: &str) -> Result<()> {
+        let result = run_script(TERMINAL_TTY_SCRIPT, &[&session.id, message])
+            .map_err(|e| anyhow::anyhow!("Terminal.app TTY error: {}", e))?;
+
+        if result == "false" {
+            return Err(anyhow::anyhow!("TTY not found in Terminal.app"));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Ghostty;
+
+impl TerminalBackend for Ghostty {
+    fn name(&self) -> &'static str {
+        "ghostty"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ghostty front window via System Events"
+    }
+
+    fn detect(&self) -> bool {
+        !super::pids_by_name("ghostty").is_empty()
+    }
+
+    // Ghostty has no scripting API, so the best we can do is tell which
+    // Ghostty process Claude runs under and paste into its front window
+    fn list_sessions(&self, _claude_pids: &[i32]) -> Result<Vec<BackendSession>> {
+        Ok(super::pids_by_name("ghostty")
+            .into_iter()
+            .map(|pid| BackendSession {
+                id: pid.to_string(),
+                pid: Some(pid),
+                tty: None,
+                title: None,
+            })
+            .collect())
+    }
+
+    fn deliver(&self, _session: &BackendSession, message: &str) -> Result<()> {
+        let result = run_script(GHOSTTY_SCRIPT, &[message])
+            .map_err(|e| anyhow::anyhow!("Ghostty automation error: {}", e))?;
+
+        if result != "true" {
+            return Err(anyhow::anyhow!("No Ghostty window to send to"));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use super::{BackendSession, TerminalBackend};
+use anyhow::Result;
+use rmpv::Value;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{BufReader, BufWriter, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Minimal msgpack-RPC client for a Neovim server socket (`nvim --listen <addr>`).
+pub struct NvimClient {
+    reader: BufReader<UnixStream>,
+    writer: BufWriter<UnixStream>,
+    next_id: u32,
+}
+
+impl NvimClient {
+    pub fn connect(socket: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(socket)?;
+        stream.set_read_timeout(Some(RPC_TIMEOUT))?;
+        stream.set_write_timeout(Some(RPC_TIMEOUT))?;
+
+        Ok(NvimClient {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: BufWriter::new(stream),
+            next_id: 0,
+        })
+    }
+
+    pub fn call(&mut self, method: &str, args: Vec<Value>) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        // Request: [type=0, msgid, method, params]
+        let request = Value::Array(vec![
+            Value::from(0),
+            Value::from(id),
+            Value::from(method),
+            Value::Array(args),
+        ]);
+        rmpv::encode::write_value(&mut self.writer, &request)?;
+        self.writer.flush()?;
+
+        loop {
+            let message = rmpv::decode::read_value(&mut self.reader)?;
+            let Value::Array(parts) = message else {
+                continue;
+            };
+
+            // Response: [type=1, msgid, error, result]. Anything else is a
+            // notification or a request from nvim we don't care about.
+            if parts.len() != 4 || parts[0].as_u64() != Some(1) {
+                continue;
+            }
+            if parts[1].as_u64() != Some(id as u64) {
+                continue;
+            }
+
+            if !parts[2].is_nil() {
+                return Err(anyhow::anyhow!("nvim {} failed: {}", method, parts[2]));
+            }
+
+            return Ok(parts[3].clone());
+        }
+    }
+
+    /// Terminal channels open in this Neovim instance.
+    pub fn terminal_channels(&mut self) -> Result<Vec<TerminalChannel>> {
+        let chans = self.call("nvim_list_chans", vec![])?;
+        let mut terminals = Vec::new();
+
+        for chan in chans.as_array().into_iter().flatten() {
+            let Some(map) = chan.as_map() else {
+                continue;
+            };
+            let field = |name: &str| {
+                map.iter()
+                    .find(|(k, _)| k.as_str() == Some(name))
+                    .map(|(_, v)| v)
+            };
+
+            if field("mode").and_then(Value::as_str) != Some("terminal") {
+                continue;
+            }
+            let Some(id) = field("id").and_then(Value::as_u64) else {
+                continue;
+            };
+
+            let pty = field("pty").and_then(Value::as_str).map(str::to_string);
+            let job_pid = self
+                .call(
+                    "nvim_call_function",
+                    vec![Value::from("jobpid"), Value::Array(vec![Value::from(id)])],
+                )
+                .ok()
+                .and_then(|v| v.as_i64())
+                .map(|pid| pid as i32);
+
+            terminals.push(TerminalChannel { id, pty, job_pid });
+        }
+
+        Ok(terminals)
+    }
+
+    pub fn chansend(&mut self, channel: u64, data: &str) -> Result<()> {
+        self.call(
+            "nvim_call_function",
+            vec![
+                Value::from("chansend"),
+                Value::Array(vec![Value::from(channel), Value::from(data)]),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TerminalChannel {
+    pub id: u64,
+    pub pty: Option<String>,
+    pub job_pid: Option<i32>,
+}
+
+pub struct Nvim;
+
+impl TerminalBackend for Nvim {
+    fn name(&self) -> &'static str {
+        "nvim"
+    }
+
+    fn description(&self) -> &'static str {
+        "Neovim :terminal buffers via msgpack-RPC"
+    }
+
+    fn detect(&self) -> bool {
+        crate::process::command_exists("nvim")
+    }
+
+    // Claude is either the job itself (`:terminal claude`) or runs inside the
+    // job's shell, in which case it shares the job's pty.
+    fn list_sessions(&self, claude_pids: &[i32]) -> Result<Vec<BackendSession>> {
+        let mut sessions = Vec::new();
+
+        for socket in discover_sockets(claude_pids) {
+            let Ok(mut client) = NvimClient::connect(&socket) else {
+                continue;
+            };
+            let Ok(channels) = client.terminal_channels() else {
+                continue;
+            };
+
+            sessions.extend(channels.into_iter().map(|channel| BackendSession {
+                id: format!("{}#{}", socket.display(), channel.id),
+                pid: channel.job_pid,
+                tty: channel.pty,
+                title: None,
+            }));
+        }
+
+        Ok(sessions)
+    }
+
+    fn deliver(&self, session: &BackendSession, message: &str) -> Result<()> {
+        let (socket, channel) = session
+            .id
+            .rsplit_once('#')
+            .and_then(|(socket, chan)| Some((socket, chan.parse::<u64>().ok()?)))
+            .ok_or_else(|| anyhow::anyhow!("Invalid Neovim session: {}", session.id))?;
+
+        let mut client = NvimClient::connect(Path::new(socket))?;
+        client.chansend(channel, message)?;
+        // Send Enter separately so Claude doesn't treat it as part of a paste
+        std::thread::sleep(Duration::from_millis(50));
+        client.chansend(channel, "\r")?;
+
+        Ok(())
+    }
+}
+
+/// Candidate Neovim server sockets: `$NVIM` from the Claude processes' own
+/// environment (set by nvim for every `:terminal` job), our own `$NVIM`, and
+/// the default `--listen` locations.
+pub fn discover_sockets(pids: &[i32]) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut sockets = Vec::new();
+    let mut push = |path: PathBuf| {
+        if is_socket(&path) && seen.insert(path.clone()) {
+            sockets.push(path);
+        }
+    };
+
+    for pid in pids {
+        if let Some(addr) = nvim_env_of(*pid) {
+            push(addr);
+        }
+    }
+
+    if let Some(addr) = std::env::var_os("NVIM") {
+        push(PathBuf::from(addr));
+    }
+
+    // Neovim >= 0.9 on Linux: $XDG_RUNTIME_DIR/nvim.<pid>.0
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        for path in list_dir(Path::new(&runtime_dir)) {
+            if file_name_starts_with(&path, "nvim.") {
+                push(path);
+            }
+        }
+    }
+
+    // Elsewhere: $TMPDIR/nvim.<user>/<random>/nvim.<pid>.0
+    let user = std::env::var("USER").unwrap_or_default();
+    let base = std::env::temp_dir().join(format!("nvim.{}", user));
+    for dir in list_dir(&base) {
+        for path in list_dir(&dir) {
+            if file_name_starts_with(&path, "nvim.") {
+                push(path);
+            }
+        }
+    }
+
+    sockets
+}
+
+fn nvim_env_of(pid: i32) -> Option<PathBuf> {
+    let pid = Pid::from(pid as usize);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing().with_environ(UpdateKind::Always),
+    );
+
+    sys.process(pid)?
+        .environ()
+        .iter()
+        .filter_map(|var| var.to_str())
+        .find_map(|var| var.strip_prefix("NVIM="))
+        .map(PathBuf::from)
+}
+
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+fn file_name_starts_with(path: &Path, prefix: &str) -> bool {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .is_some_and(|name| name.starts_with(prefix))
+}
+
+fn is_socket(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.file_type().is_socket())
+}
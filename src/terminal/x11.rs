@@ -0,0 +1,126 @@
+use super::{BackendSession, TerminalBackend};
+use anyhow::Result;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::time::Duration;
+use x11rb::CURRENT_TIME;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, InputFocus, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+// Last resort for terminals without a scripting API (xterm, Alacritty, foot
+// under XWayland, ...): find the X window belonging to Claude's terminal,
+// focus it and type the message as synthetic key presses.
+
+/// Top-level X windows and the pid that owns each one (`_NET_WM_PID`).
+pub fn list_windows() -> Result<Vec<(Window, i32)>> {
+    let (conn, screen) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen].root;
+    let wm_pid = intern(&conn, "_NET_WM_PID")?;
+
+    let mut windows = Vec::new();
+    for window in client_windows(&conn, root)? {
+        let reply = conn
+            .get_property(false, window, wm_pid, AtomEnum::CARDINAL, 0, 1)?
+            .reply()?;
+        if let Some(pid) = reply.value32().and_then(|mut v| v.next()) {
+            windows.push((window, pid as i32));
+        }
+    }
+
+    Ok(windows)
+}
+
+pub struct X11;
+
+impl TerminalBackend for X11 {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn description(&self) -> &'static str {
+        "Any X11 terminal window via synthetic keystrokes"
+    }
+
+    fn detect(&self) -> bool {
+        std::env::var_os("DISPLAY").is_some() && x11rb::connect(None).is_ok()
+    }
+
+    // One session per window, owned by the terminal emulator Claude runs under
+    fn list_sessions(&self, _claude_pids: &[i32]) -> Result<Vec<BackendSession>> {
+        Ok(list_windows()?
+            .into_iter()
+            .map(|(window, pid)| BackendSession {
+                id: window.to_string(),
+                pid: Some(pid),
+                tty: None,
+                title: None,
+            })
+            .collect())
+    }
+
+    fn deliver(&self, session: &BackendSession, message: &str) -> Result<()> {
+        let window: Window = session.id.parse()?;
+
+        focus_window(window)?;
+        // Give the window manager a moment to hand over focus
+        std::thread::sleep(Duration::from_millis(200));
+
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| anyhow::anyhow!("Failed to connect to X server: {}", e))?;
+        enigo
+            .text(message)
+            .map_err(|e| anyhow::anyhow!("Failed to type message: {}", e))?;
+        std::thread::sleep(Duration::from_millis(50));
+        enigo
+            .key(Key::Return, Direction::Click)
+            .map_err(|e| anyhow::anyhow!("Failed to press Enter: {}", e))?;
+
+        Ok(())
+    }
+}
+
+fn focus_window(window: Window) -> Result<()> {
+    let (conn, screen) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen].root;
+    let active_window = intern(&conn, "_NET_ACTIVE_WINDOW")?;
+
+    // Ask the window manager nicely (source indication 2 = pager, which WMs
+    // honour without focus-stealing prevention)...
+    let event = ClientMessageEvent::new(32, window, active_window, [2, CURRENT_TIME, 0, 0, 0]);
+    conn.send_event(
+        false,
+        root,
+        EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+        event,
+    )?;
+
+    // ...and set input focus directly for when there is no window manager
+    conn.set_input_focus(InputFocus::PARENT, window, CURRENT_TIME)?;
+    conn.flush()?;
+
+    Ok(())
+}
+
+// Managed windows from the window manager's _NET_CLIENT_LIST, or the root's
+// children when no EWMH window manager is running (e.g. a bare Xvfb).
+fn client_windows(conn: &RustConnection, root: Window) -> Result<Vec<Window>> {
+    let client_list = intern(conn, "_NET_CLIENT_LIST")?;
+    let reply = conn
+        .get_property(false, root, client_list, AtomEnum::WINDOW, 0, u32::MAX)?
+        .reply()?;
+
+    if let Some(windows) = reply.value32() {
+        let windows: Vec<Window> = windows.collect();
+        if !windows.is_empty() {
+            return Ok(windows);
+        }
+    }
+
+    Ok(conn.query_tree(root)?.reply()?.children)
+}
+
+fn intern(conn: &RustConnection, name: &str) -> Result<u32> {
+    Ok(conn.intern_atom(false, name.as_bytes())?.reply()?.atom)
+}